@@ -10,9 +10,15 @@
 use indexmap::map::IndexMap;
 use serde_derive::{Deserialize, Serialize};
 //use std::collections::HashMap;
+use std::collections::HashSet;
 use std::collections::VecDeque;
 use std::fmt;
 
+// Deterministic instruction table: state -> symbol -> (write, move, next)
+type Table = IndexMap<String, IndexMap<String, (String, Move, String)>>;
+// Nondeterministic table: state -> symbol -> candidate (write, move, next) list
+type NdTable = IndexMap<String, IndexMap<String, Vec<(String, Move, String)>>>;
+
 // Implementation of a Turing machine
 #[derive(Debug, Deserialize, Serialize)]
 pub struct TuringMachine {
@@ -20,8 +26,16 @@ pub struct TuringMachine {
     steps: usize,
     // Current state of the Turing machine
     state: String,
+    // States in which the Turing machine halts
+    halts: HashSet<String>,
     // Table of instructions for the Turing machine
-    table: IndexMap<String, IndexMap<String, (String, Move, String)>>,
+    table: Table,
+    // Nondeterministic table: each state/symbol may map to several transitions
+    #[serde(default)]
+    ndtable: NdTable,
+    // Record of forward steps, enabling the machine to be stepped backwards
+    #[serde(default)]
+    history: Vec<Transition>,
     // Tape of the Turing machine
     tape: Tape,
 }
@@ -32,43 +46,386 @@ impl TuringMachine {
         self.tape.count1s()
     }
 
+    // Build a Turing machine from the textual transition notation
+    // `(current_state, read_symbol, write_symbol, move, next_state)`, one tuple
+    // per line. An optional leading header block of `key: value` lines may set
+    // the initial state, blank symbol and terminating states.
+    pub fn from_rules(initial_state: &str, rules_text: &str) -> Result<TuringMachine, ParseError> {
+        let mut state = initial_state.to_string();
+        let mut blank = "0".to_string();
+        let mut halts: HashSet<String> = HashSet::new();
+        let mut table: Table = IndexMap::new();
+
+        for (i, raw) in rules_text.lines().enumerate() {
+            let line = raw.trim();
+            // Skip empty lines and comments
+            if line.is_empty() || line.starts_with('#') || line.starts_with("//") {
+                continue;
+            }
+            // Header entries use `key: value` and do not start with a tuple
+            if !line.starts_with('(') && line.contains(':') {
+                let (key, value) = line.split_once(':').unwrap();
+                let value = value.trim();
+                match key.trim().to_lowercase().as_str() {
+                    "initial" | "start" => state = value.to_string(),
+                    "blank" => blank = value.to_string(),
+                    "halt" | "halts" | "terminating" | "final" => {
+                        for h in value.split([',', ' ']) {
+                            let h = h.trim();
+                            if !h.is_empty() {
+                                halts.insert(h.to_string());
+                            }
+                        }
+                    }
+                    _ => return Err(ParseError::BadHeader(i + 1, line.to_string())),
+                }
+                continue;
+            }
+            // Transition tuple: (current, read, write, move, next)
+            let inner = line.trim_start_matches('(').trim_end_matches(')');
+            let parts: Vec<&str> = inner.split(',').map(|p| p.trim()).collect();
+            if parts.len() != 5 {
+                return Err(ParseError::BadTuple(i + 1, line.to_string()));
+            }
+            let mov = match parts[3].to_lowercase().as_str() {
+                "left" | "l" => Move::L,
+                "right" | "r" => Move::R,
+                "stay" | "n" => Move::N,
+                _ => return Err(ParseError::BadMove(i + 1, parts[3].to_string())),
+            };
+            table
+                .entry(parts[0].to_string())
+                .or_default()
+                .insert(
+                    parts[1].to_string(),
+                    (parts[2].to_string(), mov, parts[4].to_string()),
+                );
+        }
+
+        // Default to the conventional "HALT" state if none were specified
+        if halts.is_empty() {
+            halts.insert("HALT".to_string());
+        }
+
+        Ok(TuringMachine {
+            steps: 0,
+            state,
+            halts,
+            table,
+            ndtable: IndexMap::new(),
+            history: Vec::new(),
+            tape: Tape {
+                blank: blank.clone(),
+                left: VecDeque::new(),
+                center: blank,
+                right: VecDeque::new(),
+            },
+        })
+    }
+
     // Run the Turing machine until it halts (if it halts ;) ).
-    pub fn run(&mut self) {
-        while self.state != "HALT" {
-            self.step();
+    pub fn run(&mut self) -> Result<(), TmError> {
+        while !self.halts.contains(&self.state) {
+            self.step()?;
         }
+        Ok(())
     }
 
     // Run the Turing machine until it halts (if it halts). Print every step of that.
-    pub fn run_print(&mut self) {
-        while self.state != "HALT" {
-            self.step();
+    pub fn run_print(&mut self) -> Result<(), TmError> {
+        while !self.halts.contains(&self.state) {
+            self.step()?;
             println!("{}", self);
         }
+        Ok(())
     }
 
-    // Do one step of the Turing machine.
-    pub fn step(&mut self) {
-        if self.state != "HALT" {
-            self.steps += 1;
-            // Panic if the current value is not in the table
-            let next = match self.table.get(&self.state) {
-                Some(x) => match x.get(&self.tape.center) {
-                    Some(x) => x,
-                    None => panic!("Error1"),
-                },
-                None => panic!("Error2"),
+    // Check the instruction table for completeness, reporting every reachable
+    // state/symbol combination that has no transition defined for it.
+    pub fn validate(&self) -> Result<(), Vec<TmError>> {
+        // States reachable from the initial state (halting states stop the search)
+        let mut reachable: HashSet<String> = HashSet::new();
+        let mut frontier = vec![self.state.clone()];
+        while let Some(s) = frontier.pop() {
+            if self.halts.contains(&s) || !reachable.insert(s.clone()) {
+                continue;
+            }
+            if let Some(row) = self.table.get(&s) {
+                for a in row.values() {
+                    frontier.push(a.2.clone());
+                }
+            }
+        }
+        // Every symbol that can end up on the tape
+        let mut alphabet: Vec<String> = Vec::new();
+        let mut have: HashSet<String> = HashSet::new();
+        if have.insert(self.tape.blank.clone()) {
+            alphabet.push(self.tape.blank.clone());
+        }
+        for row in self.table.values() {
+            for (read, a) in row {
+                if have.insert(read.clone()) {
+                    alphabet.push(read.clone());
+                }
+                if have.insert(a.0.clone()) {
+                    alphabet.push(a.0.clone());
+                }
+            }
+        }
+        // Candidate states in a stable order: initial, defined, then referenced
+        let mut ordered: Vec<String> = Vec::new();
+        let mut added: HashSet<String> = HashSet::new();
+        if added.insert(self.state.clone()) {
+            ordered.push(self.state.clone());
+        }
+        for s in self.table.keys() {
+            if added.insert(s.clone()) {
+                ordered.push(s.clone());
+            }
+        }
+        for row in self.table.values() {
+            for a in row.values() {
+                if added.insert(a.2.clone()) {
+                    ordered.push(a.2.clone());
+                }
+            }
+        }
+        let mut errors = Vec::new();
+        for state in &ordered {
+            if !reachable.contains(state) {
+                continue;
+            }
+            match self.table.get(state) {
+                None => errors.push(TmError::NoState(state.clone())),
+                Some(row) => {
+                    for sym in &alphabet {
+                        if !row.contains_key(sym) {
+                            errors.push(TmError::NoSymbol(state.clone(), sym.clone()));
+                        }
+                    }
+                }
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    // Run the Turing machine until it halts or its step counter (which includes
+    // steps taken before this call) reaches `max_steps`, detecting infinite
+    // loops by remembering every configuration visited during this run.
+    pub fn run_bounded(&mut self, max_steps: usize) -> Result<RunOutcome, TmError> {
+        let mut seen = HashSet::new();
+        seen.insert(self.config_key());
+        loop {
+            if self.halts.contains(&self.state) {
+                return Ok(RunOutcome::Halted(self.steps));
+            }
+            if self.steps >= max_steps {
+                return Ok(RunOutcome::StepLimit);
+            }
+            self.step()?;
+            // A deterministic machine is fully determined by its configuration,
+            // so a repeated configuration means it will never halt.
+            if !seen.insert(self.config_key()) {
+                return Ok(RunOutcome::Loop(self.steps));
+            }
+        }
+    }
+
+    // Serialize the full configuration (state and tape) into a compact key.
+    fn config_key(&self) -> String {
+        nd_config_key(&self.state, &self.tape)
+    }
+
+    // Breadth-first search over machine configurations, expanding every
+    // applicable nondeterministic transition. Returns the first configuration
+    // trace that reaches a halting state, or `None` within the step budget.
+    pub fn run_nondeterministic(&self, max_steps: usize) -> Option<Vec<String>> {
+        let mut visited: HashSet<String> = HashSet::new();
+        let mut frontier: VecDeque<(String, Tape, Vec<String>)> = VecDeque::new();
+        let key = nd_config_key(&self.state, &self.tape);
+        visited.insert(key.clone());
+        frontier.push_back((self.state.clone(), self.tape.clone(), vec![key]));
+        while let Some((state, tape, trace)) = frontier.pop_front() {
+            // A halting state anywhere on the frontier is an accepting path
+            if self.halts.contains(&state) {
+                return Some(trace);
+            }
+            // Respect the step budget (a trace of n steps holds n+1 configs)
+            if trace.len() > max_steps {
+                continue;
+            }
+            let transitions = match self.ndtable.get(&state).and_then(|r| r.get(&tape.center)) {
+                Some(t) => t,
+                None => continue,
             };
-            // Get the new value for the position
-            self.tape.center = next.0.clone();
-            // Move according to the rule
-            self.tape.mov(next.1);
-            // Set the new state according to the rule
-            self.state = next.2.to_string();
+            for (write, mov, next) in transitions {
+                let mut branch = tape.clone();
+                branch.center = write.clone();
+                branch.mov(*mov);
+                let key = nd_config_key(next, &branch);
+                if visited.insert(key.clone()) {
+                    let mut new_trace = trace.clone();
+                    new_trace.push(key);
+                    frontier.push_back((next.clone(), branch, new_trace));
+                }
+            }
+        }
+        None
+    }
+
+    // Do one step of the Turing machine.
+    pub fn step(&mut self) -> Result<(), TmError> {
+        if self.halts.contains(&self.state) {
+            return Ok(());
+        }
+        // Error out if there is no transition for the current value
+        let next = match self.table.get(&self.state) {
+            Some(x) => match x.get(&self.tape.center) {
+                Some(x) => x.clone(),
+                None => {
+                    return Err(TmError::NoSymbol(self.state.clone(), self.tape.center.clone()))
+                }
+            },
+            None => return Err(TmError::NoState(self.state.clone())),
+        };
+        // Record enough to invert this step later
+        self.history.push(Transition {
+            state: self.state.clone(),
+            read: self.tape.center.clone(),
+            mov: next.1,
+        });
+        self.steps += 1;
+        // Get the new value for the position
+        self.tape.center = next.0;
+        // Move according to the rule
+        self.tape.mov(next.1);
+        // Set the new state according to the rule
+        self.state = next.2;
+        Ok(())
+    }
+
+    // Step the machine backwards, undoing the most recent forward step. Returns
+    // `None` when there is no recorded history left to undo.
+    pub fn step_back(&mut self) -> Option<()> {
+        let t = self.history.pop()?;
+        match t.mov {
+            Move::R => {
+                // Forward pushed the written symbol onto the left deque
+                self.tape.left.pop_front();
+                let cur = self.tape.center.clone();
+                // Don't resurrect a blank that was only synthesized at the end
+                if !(self.tape.right.is_empty() && cur == self.tape.blank) {
+                    self.tape.right.push_front(cur);
+                }
+            }
+            Move::L => {
+                self.tape.right.pop_front();
+                let cur = self.tape.center.clone();
+                if !(self.tape.left.is_empty() && cur == self.tape.blank) {
+                    self.tape.left.push_front(cur);
+                }
+            }
+            Move::N => {}
+        }
+        // Restore the original symbol under the head and the previous state
+        self.tape.center = t.read;
+        self.state = t.state;
+        self.steps -= 1;
+        Some(())
+    }
+
+    // Step backwards through the whole recorded history, printing every step.
+    pub fn run_back_print(&mut self) {
+        while self.step_back().is_some() {
+            println!("{}", self);
+        }
+    }
+}
+
+// Serialize a (state, tape) configuration into a compact, comparable key.
+fn nd_config_key(state: &str, tape: &Tape) -> String {
+    format!("{}|{:?}|{}|{:?}", state, tape.left, tape.center, tape.right)
+}
+
+// Outcome of a bounded run of the Turing machine.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RunOutcome {
+    // Reached a halting state after the given number of steps
+    Halted(usize),
+    // Hit the step limit without halting
+    StepLimit,
+    // A configuration repeated at the given step, proving an infinite loop
+    Loop(usize),
+}
+
+// Errors produced while running the Turing machine.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TmError {
+    // No transitions are defined for this state at all
+    NoState(String),
+    // The state has no transition for the symbol currently under the head
+    NoSymbol(String, String),
+}
+
+impl fmt::Display for TmError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TmError::NoState(state) => {
+                write!(f, "no transition defined for state `{}`", state)
+            }
+            TmError::NoSymbol(state, symbol) => {
+                write!(f, "no transition for symbol `{}` in state `{}`", symbol, state)
+            }
         }
     }
 }
 
+impl std::error::Error for TmError {}
+
+// Errors produced while parsing a textual machine definition.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseError {
+    // A transition line did not have exactly five comma-separated fields
+    BadTuple(usize, String),
+    // The move field was not one of left/right/stay (or L/R/N)
+    BadMove(usize, String),
+    // A header line used an unknown key
+    BadHeader(usize, String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::BadTuple(line, text) => {
+                write!(f, "line {}: expected a 5-tuple, found `{}`", line, text)
+            }
+            ParseError::BadMove(line, text) => {
+                write!(f, "line {}: unknown move `{}`", line, text)
+            }
+            ParseError::BadHeader(line, text) => {
+                write!(f, "line {}: unknown header `{}`", line, text)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// A single forward step, recorded so it can be inverted by `step_back`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Transition {
+    // State the machine was in before the step
+    state: String,
+    // Symbol under the head before it was overwritten
+    read: String,
+    // Direction the head moved
+    mov: Move,
+}
+
 // Implementation of the movement instructions of the head of the tape.
 #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub enum Move {
@@ -79,8 +436,10 @@ pub enum Move {
 
 // Implementation of the tape of the Turing machine.
 // Using VecDeque to have fast speed
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Tape {
+    // Symbol produced when the head runs off either end of the tape
+    blank: String,
     left: VecDeque<String>,
     center: String,
     right: VecDeque<String>,
@@ -112,13 +471,13 @@ impl Tape {
             self.right.push_front(self.center.clone());
             self.center = match self.left.pop_front() {
                 Some(x) => x,
-                None => "0".to_string(),
+                None => self.blank.clone(),
             };
         } else if dir == Move::R {
             self.left.push_front(self.center.clone());
             self.center = match self.right.pop_front() {
                 Some(x) => x,
-                None => "0".to_string(),
+                None => self.blank.clone(),
             };
         } else if dir == Move::N {
         }